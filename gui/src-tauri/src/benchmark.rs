@@ -0,0 +1,177 @@
+// ============================================================
+// PROVIDER / ZOOM / MODEL BENCHMARK HARNESS
+// ============================================================
+//
+// Runs a fixed set of coordinates through every `{provider, zoom, radius,
+// model_name}` config under test, timing the tile fetch, AI inference,
+// and end-to-end stages separately, and writes a structured report to
+// `benchmarks/` so a user can compare configs before committing to a
+// large batch.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{fetch_stitched_tile, get_paths, run_ai_analysis};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BenchConfig {
+    pub provider: String,
+    pub zoom: u32,
+    pub radius: u32,
+    pub model_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StageStats {
+    mean_ms: f64,
+    median_ms: f64,
+    p95_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ConfigResult {
+    config: BenchConfig,
+    samples: usize,
+    failures: usize,
+    fetch: StageStats,
+    inference: StageStats,
+    end_to_end: StageStats,
+}
+
+#[derive(Debug, Serialize)]
+struct MachineInfo {
+    os: String,
+    cpu_count: usize,
+    python_path: String,
+    timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchmarkReport {
+    machine: MachineInfo,
+    results: Vec<ConfigResult>,
+}
+
+fn stats(mut samples_ms: Vec<f64>) -> StageStats {
+    if samples_ms.is_empty() {
+        return StageStats {
+            mean_ms: 0.0,
+            median_ms: 0.0,
+            p95_ms: 0.0,
+        };
+    }
+
+    samples_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = samples_ms.len();
+    let mean = samples_ms.iter().sum::<f64>() / n as f64;
+    let median = samples_ms[n / 2];
+    let p95_idx = (((n as f64) * 0.95).ceil() as usize).saturating_sub(1).min(n - 1);
+
+    StageStats {
+        mean_ms: mean,
+        median_ms: median,
+        p95_ms: samples_ms[p95_idx],
+    }
+}
+
+fn benchmarks_dir() -> Result<PathBuf, String> {
+    let (_, _, project_root) = get_paths()?;
+    let dir = project_root.join("benchmarks");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+#[tauri::command]
+pub async fn run_benchmark(
+    points: Vec<BenchPoint>,
+    configs: Vec<BenchConfig>,
+) -> Result<BenchmarkReport, String> {
+    let (_, _, project_root) = get_paths()?;
+    let python_path = project_root.join(".venv").join("bin").join("python");
+
+    let mut results = Vec::with_capacity(configs.len());
+
+    for config in &configs {
+        let mut fetch_ms = Vec::with_capacity(points.len());
+        let mut inference_ms = Vec::with_capacity(points.len());
+        let mut e2e_ms = Vec::with_capacity(points.len());
+        let mut failures = 0usize;
+
+        for point in &points {
+            let e2e_start = Instant::now();
+
+            let fetch_start = Instant::now();
+            let tile = fetch_stitched_tile(
+                point.lat,
+                point.lon,
+                config.zoom,
+                config.radius,
+                config.provider.clone(),
+                None,
+            )
+            .await;
+            let fetch_elapsed = fetch_start.elapsed();
+
+            let tile = match tile {
+                Ok(t) => t,
+                Err(_) => {
+                    failures += 1;
+                    continue;
+                }
+            };
+
+            let inference_start = Instant::now();
+            let outcome = run_ai_analysis(tile, config.model_name.clone(), None).await;
+            let inference_elapsed = inference_start.elapsed();
+
+            if outcome.is_err() {
+                failures += 1;
+                continue;
+            }
+
+            fetch_ms.push(fetch_elapsed.as_secs_f64() * 1000.0);
+            inference_ms.push(inference_elapsed.as_secs_f64() * 1000.0);
+            e2e_ms.push(e2e_start.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        results.push(ConfigResult {
+            config: config.clone(),
+            samples: points.len(),
+            failures,
+            fetch: stats(fetch_ms),
+            inference: stats(inference_ms),
+            end_to_end: stats(e2e_ms),
+        });
+    }
+
+    let report = BenchmarkReport {
+        machine: MachineInfo {
+            os: std::env::consts::OS.to_string(),
+            cpu_count: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            python_path: python_path.display().to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+        results,
+    };
+
+    let dir = benchmarks_dir()?;
+    let file_name = format!(
+        "benchmark_{}.json",
+        chrono::Local::now().format("%Y%m%d_%H%M%S%3f")
+    );
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    fs::write(dir.join(file_name), json).map_err(|e| e.to_string())?;
+
+    Ok(report)
+}
@@ -0,0 +1,119 @@
+// ============================================================
+// NDJSON FOLLOW READER
+// ============================================================
+//
+// Tails an NDJSON file the way `tail -f` follows a log: each call to
+// `poll_line` makes one non-blocking attempt to read a new complete line,
+// decodes it as a `SolarDetection`, and reports `Line::Done` once it reads
+// the `{"__batch_done__": true}` sentinel line. A malformed line is
+// treated as an error that aborts the job rather than being silently
+// skipped.
+//
+// `poll_line` does not sleep or retry itself — callers own the poll loop
+// so they can interleave other liveness checks (e.g. `child.try_wait()`)
+// between `Line::Pending` results instead of blocking indefinitely on a
+// writer that may never produce another line.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::SolarDetection;
+
+pub struct FollowReader {
+    reader: BufReader<File>,
+}
+
+/// Result of one non-blocking poll of the NDJSON file.
+pub enum Line {
+    /// A complete detection line was decoded.
+    Detection(SolarDetection),
+    /// The `{"__batch_done__": true}` sentinel was reached.
+    Done,
+    /// No complete line is available yet; try again later.
+    Pending,
+}
+
+impl FollowReader {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        Ok(Self {
+            reader: BufReader::new(file),
+        })
+    }
+
+    /// Skips past every line already in the file so `poll_line` only
+    /// reports detections appended from this point on. A resumed job reuses
+    /// the same NDJSON file its prior run wrote to, so without this the
+    /// follower would re-decode and re-emit every already-completed row.
+    pub fn seek_to_end(&mut self) -> Result<(), String> {
+        self.reader
+            .seek(SeekFrom::End(0))
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Makes one non-blocking attempt to read the next NDJSON line. Returns
+    /// `Line::Pending` immediately (without sleeping) if no complete line
+    /// has been flushed yet.
+    pub fn poll_line(&mut self) -> Result<Line, String> {
+        loop {
+            let pos_before = self.reader.stream_position().map_err(|e| e.to_string())?;
+            let mut line = String::new();
+            let bytes_read = self.reader.read_line(&mut line).map_err(|e| e.to_string())?;
+
+            if bytes_read == 0 || !line.ends_with('\n') {
+                // No complete line flushed yet; rewind past the partial
+                // read so the next poll re-reads it in full.
+                self.reader
+                    .seek(SeekFrom::Start(pos_before))
+                    .map_err(|e| e.to_string())?;
+                return Ok(Line::Pending);
+            }
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let value: Value = serde_json::from_str(trimmed)
+                .map_err(|e| format!("Bad NDJSON line {trimmed:?}: {e}"))?;
+
+            if value.get("__batch_done__").and_then(Value::as_bool) == Some(true) {
+                return Ok(Line::Done);
+            }
+
+            let detection: SolarDetection = serde_json::from_value(value)
+                .map_err(|e| format!("Bad detection line {trimmed:?}: {e}"))?;
+            return Ok(Line::Detection(detection));
+        }
+    }
+}
+
+/// Scans an NDJSON file for `sample_id`s that completed before a crash or
+/// cancel, so a resume can skip re-submitting them.
+pub fn completed_sample_ids(path: &Path) -> Result<HashSet<String>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let reader = BufReader::new(file);
+    let mut ids = HashSet::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
+            if let Some(id) = value.get("sample_id").and_then(Value::as_str) {
+                ids.insert(id.to_string());
+            }
+        }
+    }
+
+    Ok(ids)
+}
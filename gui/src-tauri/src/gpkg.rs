@@ -0,0 +1,178 @@
+// ============================================================
+// MINIMAL GEOPACKAGE WRITER
+// ============================================================
+//
+// Writes just enough of the GeoPackage spec (sqlite + gpkg_* metadata
+// tables + WKB geometry wrapped in the GPKG binary header) for QGIS/
+// ArcGIS to open the `detections` layer. Not a general-purpose encoder.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use crate::geo::mask_ring_to_lonlat;
+use crate::SolarDetection;
+
+const SRS_ID: i32 = 4326;
+
+fn point_wkb(lon: f64, lat: f64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(21);
+    buf.push(1); // little-endian
+    buf.extend_from_slice(&1u32.to_le_bytes()); // wkbPoint
+    buf.extend_from_slice(&lon.to_le_bytes());
+    buf.extend_from_slice(&lat.to_le_bytes());
+    buf
+}
+
+fn polygon_wkb(rings: &[Vec<[f64; 2]>]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.push(1);
+    buf.extend_from_slice(&3u32.to_le_bytes()); // wkbPolygon
+    buf.extend_from_slice(&(rings.len() as u32).to_le_bytes());
+    for ring in rings {
+        buf.extend_from_slice(&(ring.len() as u32).to_le_bytes());
+        for [lon, lat] in ring {
+            buf.extend_from_slice(&lon.to_le_bytes());
+            buf.extend_from_slice(&lat.to_le_bytes());
+        }
+    }
+    buf
+}
+
+// Wraps raw WKB in the GPKG binary header (magic, version, flags, SRS id).
+fn gpkg_geom(wkb: Vec<u8>) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(8 + wkb.len());
+    buf.extend_from_slice(b"GP"); // magic
+    buf.push(0); // version
+    buf.push(0b0000_0001); // flags: little-endian, no envelope
+    buf.extend_from_slice(&SRS_ID.to_le_bytes());
+    buf.extend(wkb);
+    buf
+}
+
+fn detection_geometry_wkb(d: &SolarDetection) -> Vec<u8> {
+    if d.bbox_or_mask.is_empty() {
+        gpkg_geom(point_wkb(d.lon, d.lat))
+    } else {
+        let rings: Vec<Vec<[f64; 2]>> = d
+            .bbox_or_mask
+            .iter()
+            .map(|ring| mask_ring_to_lonlat(ring, d.lat, d.lon, d.zoom, d.radius))
+            .collect();
+        if rings.len() == 1 {
+            return gpkg_geom(polygon_wkb(&rings));
+        }
+        // Independent rings, as in the GeoJSON export: one Polygon per ring
+        // inside a MultiPolygon, not one Polygon with holes.
+        let mut wkb = vec![1];
+        wkb.extend_from_slice(&6u32.to_le_bytes()); // wkbMultiPolygon
+        wkb.extend_from_slice(&(rings.len() as u32).to_le_bytes());
+        for ring in &rings {
+            wkb.extend(polygon_wkb(std::slice::from_ref(ring)));
+        }
+        gpkg_geom(wkb)
+    }
+}
+
+pub fn write_detections(out_path: &Path, detections: &[SolarDetection]) -> Result<(), String> {
+    if out_path.exists() {
+        std::fs::remove_file(out_path).map_err(|e| e.to_string())?;
+    }
+
+    let conn = Connection::open(out_path).map_err(|e| e.to_string())?;
+
+    // The GeoPackage spec requires both of these so GDAL/QGIS/ArcGIS can
+    // tell a `.gpkg` apart from a plain SQLite file; without them the GPKG
+    // driver refuses to open it. `application_id` is the ASCII "GPKG" magic
+    // (big-endian, as `PRAGMA application_id` stores it); `user_version`
+    // encodes GeoPackage version 1.2.0 as 10200 per the spec.
+    conn.pragma_update(None, "application_id", 0x4750_4B47_i32)
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "user_version", 10_200_i32)
+        .map_err(|e| e.to_string())?;
+
+    conn.execute_batch(
+        "CREATE TABLE gpkg_spatial_ref_sys (
+            srs_name TEXT NOT NULL,
+            srs_id INTEGER NOT NULL PRIMARY KEY,
+            organization TEXT NOT NULL,
+            organization_coordsys_id INTEGER NOT NULL,
+            definition TEXT NOT NULL,
+            description TEXT
+        );
+        CREATE TABLE gpkg_contents (
+            table_name TEXT NOT NULL PRIMARY KEY,
+            data_type TEXT NOT NULL,
+            identifier TEXT,
+            min_x DOUBLE, min_y DOUBLE, max_x DOUBLE, max_y DOUBLE,
+            srs_id INTEGER
+        );
+        CREATE TABLE gpkg_geometry_columns (
+            table_name TEXT NOT NULL,
+            column_name TEXT NOT NULL,
+            geometry_type_name TEXT NOT NULL,
+            srs_id INTEGER NOT NULL,
+            z TINYINT NOT NULL,
+            m TINYINT NOT NULL,
+            PRIMARY KEY (table_name, column_name)
+        );
+        CREATE TABLE detections (
+            fid INTEGER PRIMARY KEY AUTOINCREMENT,
+            geom BLOB,
+            sample_id TEXT,
+            confidence DOUBLE,
+            panel_count_est INTEGER,
+            pv_area_sqm_est DOUBLE,
+            capacity_kw_est DOUBLE,
+            has_solar INTEGER,
+            qc_status TEXT
+        );",
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO gpkg_spatial_ref_sys VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            "WGS 84",
+            SRS_ID,
+            "EPSG",
+            SRS_ID,
+            "GEOGCS[\"WGS 84\",DATUM[\"WGS_1984\",SPHEROID[\"WGS 84\",6378137,298.257223563]],PRIMEM[\"Greenwich\",0],UNIT[\"degree\",0.0174532925199433]]",
+            "longitude/latitude",
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO gpkg_contents (table_name, data_type, identifier, srs_id) VALUES ('detections', 'features', 'detections', ?1)",
+        rusqlite::params![SRS_ID],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO gpkg_geometry_columns VALUES ('detections', 'geom', 'GEOMETRY', ?1, 0, 0)",
+        rusqlite::params![SRS_ID],
+    )
+    .map_err(|e| e.to_string())?;
+
+    for d in detections {
+        conn.execute(
+            "INSERT INTO detections
+                (geom, sample_id, confidence, panel_count_est, pv_area_sqm_est, capacity_kw_est, has_solar, qc_status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                detection_geometry_wkb(d),
+                d.sample_id,
+                d.confidence,
+                d.panel_count_est as i64,
+                d.pv_area_sqm_est,
+                d.capacity_kw_est,
+                d.has_solar,
+                d.qc_status,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
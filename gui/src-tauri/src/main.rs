@@ -1,17 +1,36 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{env, fs, path::PathBuf, process::Command};
+use std::{env, fs, path::PathBuf, process::Stdio, sync::Arc};
+
 use tauri::{command, Manager};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tracing::Instrument;
+
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+mod benchmark;
+mod export;
+mod follow;
+mod geo;
+mod gpkg;
+mod inference;
+mod jobmanager;
+mod jobs;
+mod logging;
+mod models;
+mod signing;
+mod storage;
+mod streaming;
+
 
 // ============================================================
 // DATA STRUCTURES
 // ============================================================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct SolarDetection {
     sample_id: String,
     lat: f64,
@@ -27,17 +46,18 @@ struct SolarDetection {
     zoom: u32,
     radius: u32,
     provider: String,
+    model_name: String,
     audit_overlay_path: Option<String>,
     image_metadata: Option<ImageMetadata>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ImageMetadata {
     source: String,
     capture_date: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct CsvRow {
     sample_id: String,
     lat: f64,
@@ -80,7 +100,7 @@ fn fetch_and_crop_tile(
     let python_path = project_root.join(".venv").join("bin").join("python");
     let script_path = gui_dir.join("imagenRunner.py");
 
-    let output = Command::new(&python_path)
+    let output = std::process::Command::new(&python_path)
         .current_dir(&gui_dir)
         .arg(&script_path)
         .arg(lat.to_string())
@@ -117,13 +137,87 @@ fn fetch_and_crop_tile(
 }
 
 
+// Spawns `child`, registers it under `job_id` (if the caller supplied one)
+// for the duration of the wait so a concurrent `cancel_job(job_id)` call
+// can kill it, then returns its status plus captured stdout/stderr. Runs
+// under a span that tags the log lines emitted while waiting on the child
+// with its `job_id`; the child's own stdout/stderr are returned to the
+// caller, not written to the log.
+async fn run_tracked(
+    mut child: Child,
+    job_id: &Option<String>,
+) -> Result<(std::process::ExitStatus, String, String), String> {
+    let span = tracing::info_span!("subprocess", job_id = job_id.as_deref().unwrap_or("-"));
+
+    async move {
+        use tokio::io::AsyncReadExt;
+
+        let mut stdout_pipe = child.stdout.take();
+        let mut stderr_pipe = child.stderr.take();
+        let handle = Arc::new(Mutex::new(child));
+
+        if let Some(id) = job_id {
+            jobmanager::register(id.clone(), handle.clone()).await;
+        }
+
+        // The child's stdout/stderr pipes must be drained *while* we wait,
+        // not after: `run_model.py` can write more than the OS pipe buffer
+        // (a large polygon mask easily does), and with `Stdio::piped()` the
+        // child blocks on write once that buffer fills. Waiting to completion
+        // before reading the pipes deadlocks the child against us.
+        tracing::debug!("waiting on subprocess");
+        // Poll with `try_wait` instead of holding the lock across `wait()`,
+        // so `cancel_job` can take the lock and kill the child meanwhile.
+        let wait_fut = async {
+            loop {
+                let status = handle
+                    .lock()
+                    .await
+                    .try_wait()
+                    .map_err(|e| format!("Failed to wait on child process: {e}"))?;
+                if let Some(status) = status {
+                    return Ok::<_, String>(status);
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        };
+        let stdout_fut = async {
+            let mut buf = String::new();
+            if let Some(pipe) = stdout_pipe.as_mut() {
+                pipe.read_to_string(&mut buf).await.map_err(|e| e.to_string())?;
+            }
+            Ok::<_, String>(buf)
+        };
+        let stderr_fut = async {
+            let mut buf = String::new();
+            if let Some(pipe) = stderr_pipe.as_mut() {
+                pipe.read_to_string(&mut buf).await.map_err(|e| e.to_string())?;
+            }
+            Ok::<_, String>(buf)
+        };
+        let result = tokio::try_join!(wait_fut, stdout_fut, stderr_fut);
+
+        if let Some(id) = job_id {
+            jobmanager::remove(id).await;
+        }
+
+        let (status, stdout, stderr) = result?;
+        tracing::debug!(success = status.success(), "subprocess finished");
+
+        Ok((status, stdout, stderr))
+    }
+    .instrument(span)
+    .await
+}
+
 #[command]
-fn fetch_stitched_tile(
+async fn fetch_stitched_tile(
     lat: f64,
     lon: f64,
     zoom: u32,
     radius: u32,
     provider: String,
+    job_id: Option<String>,
 ) -> Result<String, String> {
     let (_, gui_dir, project_root) = get_paths()?;
 
@@ -143,10 +237,9 @@ fn fetch_stitched_tile(
         ));
     }
 
-    println!("Using python: {}", python_path.display());
-    println!("Using script: {}", script_path.display());
+    tracing::info!(python = %python_path.display(), script = %script_path.display(), "fetching stitched tile");
 
-    let output = Command::new(&python_path)
+    let child = Command::new(&python_path)
         .current_dir(&gui_dir)
         .arg(&script_path)
         .arg(lat.to_string())
@@ -155,15 +248,17 @@ fn fetch_stitched_tile(
         .arg(radius.to_string())
         .arg(&provider)
         .arg("--crop")  // ADD CROP FLAG
-        .output()
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to spawn python: {e}"))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    let (status, stdout, stderr) = run_tracked(child, &job_id).await?;
+
+    if !status.success() {
         return Err(format!("Python failed: {stderr}"));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
     let rel_path = stdout.lines().last().unwrap_or("").trim().to_string();
 
     if rel_path.is_empty() {
@@ -171,73 +266,85 @@ fn fetch_stitched_tile(
     }
 
     let img_path: PathBuf = gui_dir.join(&rel_path);
-    println!("Python output path: {}", img_path.display());
+    tracing::debug!(path = %img_path.display(), "python output path");
 
     if !img_path.exists() {
         return Err(format!("Image file not found at {}", img_path.display()));
     }
 
-    let bytes = fs::read(&img_path).map_err(|e| format!("Failed to read PNG: {e}"))?;
+    let bytes = tokio::fs::read(&img_path)
+        .await
+        .map_err(|e| format!("Failed to read PNG: {e}"))?;
     let b64 = general_purpose::STANDARD.encode(bytes);
 
     Ok(format!("data:image/png;base64,{}", b64))
 }
 
 #[command]
-fn run_ai_analysis(image_b64: String) -> Result<String, String> {
+async fn run_ai_analysis(
+    image_b64: String,
+    model_name: String,
+    job_id: Option<String>,
+) -> Result<String, String> {
     let (_, gui_dir, project_root) = get_paths()?;
+    let model_entry = models::resolve(&gui_dir, &model_name)?;
+
+    #[cfg(feature = "native-inference")]
+    if inference::use_native() {
+        let model = gui_dir.join(
+            PathBuf::from(&model_entry.weights_path).with_extension("onnx"),
+        );
+        let image_bytes = inference::decode_png(&image_b64)?;
+        let detection = inference::native::run(&image_bytes, &model, model_entry.input_size)?;
+        return serde_json::to_string(&detection).map_err(|e| e.to_string());
+    }
+
     let tmp_path = gui_dir.join("tmp_input.png");
 
     let image_bytes = general_purpose::STANDARD
         .decode(image_b64.replace("data:image/png;base64,", ""))
         .map_err(|e| e.to_string())?;
 
-    fs::write(&tmp_path, image_bytes)
+    tokio::fs::write(&tmp_path, image_bytes)
+        .await
         .map_err(|e| format!("Failed to write temp PNG: {e}"))?;
 
     let script = gui_dir.join("run_model.py");
-    let model = gui_dir.join("verifier1.pt");
+    let model = gui_dir.join(&model_entry.weights_path);
 
     let python_path = project_root.join(".venv").join("bin").join("python");
 
-    let output = Command::new(&python_path)
+    let child = Command::new(&python_path)
         .arg(&script)
         .arg(&tmp_path)
         .arg(&model)
-        .output()
+        .arg(model_entry.default_confidence_threshold.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to run AI script: {e}"))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    if !output.status.success() {
-    let err_msg = if stderr.trim().is_empty() {
-        "Unknown error from AI script".to_string()
-    } else {
-        stderr.to_string()
-    };
-    return Err(format!("AI script error: {}", err_msg));
-}
+    let (status, stdout, stderr) = run_tracked(child, &job_id).await?;
 
-    if !output.status.success() {
-        return Err(format!("AI script error: {}", stdout));
+    if !status.success() {
+        let err_msg = if stderr.trim().is_empty() {
+            "Unknown error from AI script".to_string()
+        } else {
+            stderr
+        };
+        return Err(format!("AI script error: {}", err_msg));
     }
 
-    Ok(stdout.to_string())
+    Ok(stdout)
 }
 
 // NEW: Load overlay image as base64
 #[command]
 fn load_overlay_image(image_path: String) -> Result<String, String> {
-    let path = PathBuf::from(&image_path);
-    
-    if !path.exists() {
-        return Err(format!("Overlay image not found: {}", image_path));
-    }
-    
-    let bytes = fs::read(&path)
-        .map_err(|e| format!("Failed to read overlay: {e}"))?;
-    
+    let (_, _, project_root) = get_paths()?;
+    let backend = storage::default_backend(project_root)?;
+
+    let bytes = backend.read(&image_path)?;
     let b64 = general_purpose::STANDARD.encode(bytes);
     Ok(format!("data:image/png;base64,{}", b64))
 }
@@ -248,19 +355,19 @@ fn save_detection_json(
     filename: String,
 ) -> Result<String, String> {
     let (_, _, project_root) = get_paths()?;
-    
-    let output_dir = project_root.join("detections");
-    fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-    
-    let output_path = output_dir.join(&filename);
-    
+    let backend = storage::default_backend(project_root.clone())?;
+
+    let key = format!("detections/{filename}");
     let json_string = serde_json::to_string_pretty(&data)
         .map_err(|e| format!("Failed to serialize: {}", e))?;
-    
-    fs::write(&output_path, json_string)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    
-    Ok(output_path.to_string_lossy().to_string())
+
+    backend.write(&key, json_string.as_bytes())?;
+
+    let envelope = signing::sign(&data, &project_root)?;
+    let envelope_json = serde_json::to_string_pretty(&envelope).map_err(|e| e.to_string())?;
+    backend.write(&signing::sidecar_key(&key), envelope_json.as_bytes())?;
+
+    Ok(key)
 }
 
 #[command]
@@ -269,21 +376,21 @@ fn save_batch_results(
     batch_name: String,
 ) -> Result<String, String> {
     let (_, _, project_root) = get_paths()?;
-    
-    let output_dir = project_root.join("batch_results");
-    fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-    
+    let backend = storage::default_backend(project_root.clone())?;
+
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("{}_{}.json", batch_name, timestamp);
-    let output_path = output_dir.join(&filename);
-    
+    let key = format!("batch_results/{}_{}.json", batch_name, timestamp);
+
     let json_string = serde_json::to_string_pretty(&detections)
         .map_err(|e| format!("Failed to serialize: {}", e))?;
-    
-    fs::write(&output_path, json_string)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    
-    Ok(output_path.to_string_lossy().to_string())
+
+    backend.write(&key, json_string.as_bytes())?;
+
+    let envelope = signing::sign(&detections, &project_root)?;
+    let envelope_json = serde_json::to_string_pretty(&envelope).map_err(|e| e.to_string())?;
+    backend.write(&signing::sidecar_key(&key), envelope_json.as_bytes())?;
+
+    Ok(key)
 }
 
 #[command]
@@ -292,107 +399,41 @@ fn save_audit_overlay(
     sample_id: String,
 ) -> Result<String, String> {
     let (_, _, project_root) = get_paths()?;
-    
-    let output_dir = project_root.join("audit_overlays");
-    fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-    
+    let backend = storage::default_backend(project_root)?;
+
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("audit_{}_{}.png", sample_id, timestamp);
-    let output_path = output_dir.join(&filename);
-    
-    fs::copy(&image_path, &output_path)
-        .map_err(|e| format!("Failed to copy audit overlay: {}", e))?;
-    
-    Ok(output_path.to_string_lossy().to_string())
-}
+    let key = format!("audit_overlays/audit_{}_{}.png", sample_id, timestamp);
 
-#[command]
-fn process_csv_batch(
-    csv_path: String,
-    zoom: u32,
-    radius: u32,
-    provider: String,
-) -> Result<Vec<SolarDetection>, String> {
-    use csv::ReaderBuilder;
+    let bytes = fs::read(&image_path)
+        .map_err(|e| format!("Failed to read source overlay: {e}"))?;
+    backend.write(&key, &bytes)?;
 
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(&csv_path)
-        .map_err(|e| format!("Failed to read CSV: {}", e))?;
-    
-    let mut results = Vec::new();
-    
-    for result in reader.deserialize() {
-        let row: CsvRow = result.map_err(|e| format!("Failed to parse row: {}", e))?;
-        
-        // Fetch tile
-        let tile_b64 = fetch_stitched_tile(row.lat, row.lon, zoom, radius, provider.clone())?;
-        
-        // Run AI
-        let ai_json = run_ai_analysis(tile_b64)?;
-        
-        // Parse only JSON line
-        let json_line = ai_json
-            .lines()
-            .find(|line| line.trim().starts_with('{'))
-            .ok_or("No JSON in AI output")?;
-        
-        let mut detection: SolarDetection = serde_json::from_str(json_line)
-            .map_err(|e| format!("Failed to parse AI result: {}", e))?;
-        
-        detection.sample_id = row.sample_id;
-        detection.lat = row.lat;
-        detection.lon = row.lon;
-        detection.zoom = zoom;
-        detection.radius = radius;
-        detection.provider = provider.clone();
-        
-        results.push(detection);
-    }
-    
-    Ok(results)
+    Ok(key)
 }
 
 #[command]
 fn clear_tile_cache() -> Result<String, String> {
     let (_, gui_dir, _) = get_paths()?;
-    let cache_dir = gui_dir.join("tile_cache");
-    
-    if cache_dir.exists() {
-        fs::remove_dir_all(&cache_dir)
-            .map_err(|e| format!("Failed to clear cache: {}", e))?;
-    }
-    
+    let backend = storage::default_backend(gui_dir)?;
+
+    backend.delete("tile_cache")?;
+
     Ok("Cache cleared successfully".to_string())
 }
 
 #[command]
-fn get_cache_size() -> Result<u64, String> {
+async fn get_cache_size() -> Result<u64, String> {
     let (_, gui_dir, _) = get_paths()?;
-    let cache_dir = gui_dir.join("tile_cache");
-    
-    if !cache_dir.exists() {
-        return Ok(0);
-    }
-    
-    let mut total_size = 0u64;
-    
-    fn walk_dir(dir: &PathBuf, total: &mut u64) -> std::io::Result<()> {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                walk_dir(&path, total)?;
-            } else {
-                *total += entry.metadata()?.len();
-            }
-        }
-        Ok(())
-    }
-    
-    walk_dir(&cache_dir, &mut total_size).map_err(|e| e.to_string())?;
-    
-    Ok(total_size)
+
+    // `StorageBackend` is a sync trait (the S3 variant blocks on its own
+    // internal runtime), so run it on the blocking pool rather than the
+    // async worker that's servicing other commands.
+    tauri::async_runtime::spawn_blocking(move || {
+        let backend = storage::default_backend(gui_dir)?;
+        backend.size("tile_cache")
+    })
+    .await
+    .map_err(|e| format!("Cache size task panicked: {e}"))?
 }
 
 #[command]
@@ -409,6 +450,7 @@ fn add_to_training_data(detection: SolarDetection) -> Result<String, String> {
     
     let sample = json!({
         "timestamp": chrono::Local::now().to_rfc3339(),
+        "model_name": detection.model_name,
         "confidence": detection.confidence,
         "panel_count": detection.panel_count_est,
         "area_sqm": detection.pv_area_sqm_est,
@@ -434,6 +476,9 @@ fn add_to_training_data(detection: SolarDetection) -> Result<String, String> {
 // ============================================================
 
 fn main() {
+    let (_, _, project_root) = get_paths().expect("Failed to resolve project paths");
+    let _log_guard = logging::init(&project_root);
+
     tauri::Builder::default()
         .setup(|app| {
             if let Some(win) = app.get_webview_window("main") {
@@ -449,7 +494,17 @@ fn main() {
             save_detection_json,
             save_batch_results,
             save_audit_overlay,
-            process_csv_batch,
+            jobs::start_batch_job,
+            jobs::resume_batch_job,
+            jobs::list_batch_jobs,
+            jobs::cancel_batch_job,
+            streaming::start_streaming_batch,
+            streaming::resume_batch,
+            export::export_detections,
+            signing::verify_detection,
+            jobmanager::cancel_job,
+            models::list_models,
+            benchmark::run_benchmark,
             clear_tile_cache,
             get_cache_size
         ])
@@ -0,0 +1,141 @@
+// ============================================================
+// DETECTION EXPORT (GeoJSON / CSV / GeoPackage)
+// ============================================================
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::{json, Value};
+use tauri::command;
+
+use crate::geo::mask_ring_to_lonlat;
+use crate::gpkg;
+use crate::SolarDetection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    GeoJson,
+    Csv,
+    GeoPackage,
+}
+
+impl ExportFormat {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "geojson" => Ok(ExportFormat::GeoJson),
+            "csv" => Ok(ExportFormat::Csv),
+            "geopackage" | "gpkg" => Ok(ExportFormat::GeoPackage),
+            other => Err(format!("Unsupported export format: {other}")),
+        }
+    }
+}
+
+fn detection_geometry(d: &SolarDetection) -> Value {
+    if d.bbox_or_mask.is_empty() {
+        return json!({ "type": "Point", "coordinates": [d.lon, d.lat] });
+    }
+
+    let rings: Vec<Vec<[f64; 2]>> = d
+        .bbox_or_mask
+        .iter()
+        .map(|ring| mask_ring_to_lonlat(ring, d.lat, d.lon, d.zoom, d.radius))
+        .collect();
+
+    if rings.len() == 1 {
+        json!({ "type": "Polygon", "coordinates": rings })
+    } else {
+        // Each entry in `bbox_or_mask` is an independent ring (the model
+        // can return disconnected panel clusters), not a hole in a single
+        // shape, so more than one ring becomes a MultiPolygon rather than
+        // one Polygon with holes.
+        let polygons: Vec<Vec<Vec<[f64; 2]>>> = rings.into_iter().map(|ring| vec![ring]).collect();
+        json!({ "type": "MultiPolygon", "coordinates": polygons })
+    }
+}
+
+fn detection_properties(d: &SolarDetection) -> Value {
+    json!({
+        "confidence": d.confidence,
+        "panel_count_est": d.panel_count_est,
+        "pv_area_sqm_est": d.pv_area_sqm_est,
+        "capacity_kw_est": d.capacity_kw_est,
+        "has_solar": d.has_solar,
+        "qc_status": d.qc_status,
+        "provider": d.provider,
+    })
+}
+
+fn to_geojson(detections: &[SolarDetection]) -> Value {
+    let features: Vec<Value> = detections
+        .iter()
+        .map(|d| {
+            json!({
+                "type": "Feature",
+                "geometry": detection_geometry(d),
+                "properties": detection_properties(d),
+            })
+        })
+        .collect();
+
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+fn to_csv(detections: &[SolarDetection]) -> Result<Vec<u8>, String> {
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    wtr.write_record([
+        "sample_id",
+        "lat",
+        "lon",
+        "confidence",
+        "panel_count_est",
+        "pv_area_sqm_est",
+        "capacity_kw_est",
+        "has_solar",
+        "qc_status",
+        "provider",
+    ])
+    .map_err(|e| e.to_string())?;
+
+    for d in detections {
+        wtr.write_record(&[
+            d.sample_id.clone(),
+            d.lat.to_string(),
+            d.lon.to_string(),
+            d.confidence.to_string(),
+            d.panel_count_est.to_string(),
+            d.pv_area_sqm_est.to_string(),
+            d.capacity_kw_est.to_string(),
+            d.has_solar.to_string(),
+            d.qc_status.clone(),
+            d.provider.clone(),
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+
+    wtr.into_inner().map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn export_detections(
+    detections: Vec<SolarDetection>,
+    format: String,
+    out_path: String,
+) -> Result<String, String> {
+    let format = ExportFormat::parse(&format)?;
+    let path = Path::new(&out_path);
+
+    match format {
+        ExportFormat::GeoJson => {
+            let geojson = to_geojson(&detections);
+            let text = serde_json::to_string_pretty(&geojson).map_err(|e| e.to_string())?;
+            fs::write(path, text).map_err(|e| e.to_string())?;
+        }
+        ExportFormat::Csv => {
+            let bytes = to_csv(&detections)?;
+            fs::write(path, bytes).map_err(|e| e.to_string())?;
+        }
+        ExportFormat::GeoPackage => gpkg::write_detections(path, &detections)?,
+    }
+
+    Ok(out_path)
+}
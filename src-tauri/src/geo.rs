@@ -0,0 +1,61 @@
+// ============================================================
+// WEB MERCATOR TILE MATH
+// ============================================================
+//
+// Converts the pixel-space masks stored in `SolarDetection::bbox_or_mask`
+// back into lon/lat. A mask ring is recorded in the stitched tile image's
+// pixel space, which is centered on the detection's (lat, lon) at the zoom
+// level the tile was fetched at and spans `2 * radius` pixels on a side.
+
+const TILE_SIZE: f64 = 256.0;
+
+fn world_px(lon: f64, lat: f64, zoom: u32) -> (f64, f64) {
+    let scale = TILE_SIZE * 2f64.powi(zoom as i32);
+    let x = (lon + 180.0) / 360.0 * scale;
+    let lat_rad = lat.to_radians();
+    let y = (1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / std::f64::consts::PI) / 2.0 * scale;
+    (x, y)
+}
+
+fn lonlat_from_world_px(x: f64, y: f64, zoom: u32) -> (f64, f64) {
+    let scale = TILE_SIZE * 2f64.powi(zoom as i32);
+    let lon = x / scale * 360.0 - 180.0;
+    let n = std::f64::consts::PI - 2.0 * std::f64::consts::PI * y / scale;
+    let lat = n.sinh().atan().to_degrees();
+    (lon, lat)
+}
+
+/// Convert one ring of `[px_x, px_y]` points, relative to the top-left
+/// corner of a `2*radius`-pixel-wide tile centered on `(center_lat,
+/// center_lon)`, into `[lon, lat]` pairs. The result is always closed
+/// (first point repeated as the last) as GeoJSON and WKB linear rings
+/// require, even if the model's mask ring wasn't already closed.
+pub fn mask_ring_to_lonlat(
+    ring: &[Vec<f64>],
+    center_lat: f64,
+    center_lon: f64,
+    zoom: u32,
+    radius: u32,
+) -> Vec<[f64; 2]> {
+    let (center_x, center_y) = world_px(center_lon, center_lat, zoom);
+    let origin_x = center_x - radius as f64;
+    let origin_y = center_y - radius as f64;
+
+    let mut points: Vec<[f64; 2]> = ring
+        .iter()
+        .map(|point| {
+            let px = point.first().copied().unwrap_or(0.0);
+            let py = point.get(1).copied().unwrap_or(0.0);
+            let (lon, lat) = lonlat_from_world_px(origin_x + px, origin_y + py, zoom);
+            [lon, lat]
+        })
+        .collect();
+
+    if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+        if first != last {
+            points.push(first);
+        }
+    }
+
+    points
+}
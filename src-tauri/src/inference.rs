@@ -0,0 +1,99 @@
+// ============================================================
+// INFERENCE BACKEND (python subprocess vs native in-process)
+// ============================================================
+//
+// `run_ai_analysis` always shelled out to python3 and round-tripped a PNG
+// through disk per image, which is slow in batch runs and depends on a
+// hardcoded python3 path. With the `native-inference` feature, the `.onnx`
+// export of the registry-selected model (e.g. `verifier1.onnx`) runs
+// in-process via `ort` instead;
+// set `SOLARVERIFY_FORCE_PYTHON=1` to fall back to the python script even
+// when the feature is compiled in (useful for A/B comparison).
+
+use base64::{engine::general_purpose, Engine as _};
+
+pub fn decode_png(image_b64: &str) -> Result<Vec<u8>, String> {
+    general_purpose::STANDARD
+        .decode(image_b64.replace("data:image/png;base64,", ""))
+        .map_err(|e| e.to_string())
+}
+
+pub fn use_native() -> bool {
+    cfg!(feature = "native-inference") && std::env::var("SOLARVERIFY_FORCE_PYTHON").is_err()
+}
+
+#[cfg(feature = "native-inference")]
+pub mod native {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, OnceLock};
+
+    use ort::{inputs, GraphOptimizationLevel, Session};
+
+    use crate::SolarDetection;
+
+    // Keyed by model path rather than a single global: switching models
+    // (e.g. across a benchmark run comparing weights) must actually load
+    // and run the requested weights instead of silently reusing whichever
+    // model happened to load first.
+    fn sessions() -> &'static Mutex<HashMap<PathBuf, Session>> {
+        static SESSIONS: OnceLock<Mutex<HashMap<PathBuf, Session>>> = OnceLock::new();
+        SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+    }
+
+    fn with_session<R>(
+        model_path: &Path,
+        f: impl FnOnce(&Session) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let mut sessions = sessions().lock().map_err(|e| e.to_string())?;
+        if !sessions.contains_key(model_path) {
+            let built = Session::builder()
+                .and_then(|b| b.with_optimization_level(GraphOptimizationLevel::Level3))
+                .and_then(|b| b.commit_from_file(model_path))
+                .map_err(|e| e.to_string())?;
+            sessions.insert(model_path.to_path_buf(), built);
+        }
+        f(sessions.get(model_path).expect("just inserted"))
+    }
+
+    // Matches `run_model.py`'s preprocessing: resize to the model's square
+    // input size, normalize to [0, 1], NCHW layout.
+    fn preprocess(image_bytes: &[u8], input_size: u32) -> Result<ndarray::Array4<f32>, String> {
+        let img = image::load_from_memory(image_bytes)
+            .map_err(|e| format!("Failed to decode PNG: {e}"))?
+            .resize_exact(input_size, input_size, image::imageops::FilterType::Triangle)
+            .to_rgb8();
+
+        let mut tensor =
+            ndarray::Array4::<f32>::zeros((1, 3, input_size as usize, input_size as usize));
+        for (x, y, pixel) in img.enumerate_pixels() {
+            for c in 0..3 {
+                tensor[[0, c, y as usize, x as usize]] = pixel[c] as f32 / 255.0;
+            }
+        }
+        Ok(tensor)
+    }
+
+    pub fn run(
+        image_bytes: &[u8],
+        model_path: &Path,
+        input_size: u32,
+    ) -> Result<SolarDetection, String> {
+        let tensor = preprocess(image_bytes, input_size)?;
+
+        with_session(model_path, |session| {
+            let outputs = session
+                .run(inputs!["image" => tensor.view()].map_err(|e| e.to_string())?)
+                .map_err(|e| format!("ONNX inference failed: {e}"))?;
+
+            let json_bytes = outputs["detection_json"]
+                .try_extract_raw_tensor::<u8>()
+                .map_err(|e| e.to_string())?
+                .1;
+            let json_str = std::str::from_utf8(json_bytes).map_err(|e| e.to_string())?;
+
+            serde_json::from_str(json_str)
+                .map_err(|e| format!("Failed to parse native output: {e}"))
+        })
+    }
+}
@@ -0,0 +1,41 @@
+// ============================================================
+// SUBPROCESS JOB REGISTRY
+// ============================================================
+//
+// Tracks in-flight python child processes by job_id so a frontend-issued
+// `cancel_job` can kill a `fetch_stitched_tile` / `run_ai_analysis` call
+// (or one row of a concurrent batch) while it's still blocking a worker
+// thread. Callers that don't pass a `job_id` are simply never registered
+// and can't be cancelled mid-flight. `tokio::process::Child::kill` is
+// async, so the registry (and every fn that touches it) uses tokio's
+// async `Mutex` rather than `std::sync::Mutex`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::process::Child;
+use tokio::sync::{Mutex, OnceCell};
+
+async fn children() -> &'static Mutex<HashMap<String, Arc<Mutex<Child>>>> {
+    static CHILDREN: OnceCell<Mutex<HashMap<String, Arc<Mutex<Child>>>>> = OnceCell::const_new();
+    CHILDREN
+        .get_or_init(|| async { Mutex::new(HashMap::new()) })
+        .await
+}
+
+pub async fn register(job_id: String, child: Arc<Mutex<Child>>) {
+    children().await.lock().await.insert(job_id, child);
+}
+
+pub async fn remove(job_id: &str) {
+    children().await.lock().await.remove(job_id);
+}
+
+#[tauri::command]
+pub async fn cancel_job(job_id: String) -> Result<(), String> {
+    let child = children().await.lock().await.get(&job_id).cloned();
+    match child {
+        Some(child) => child.lock().await.kill().await.map_err(|e| e.to_string()),
+        None => Err(format!("No running subprocess for job {job_id}")),
+    }
+}
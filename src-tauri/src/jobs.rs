@@ -0,0 +1,295 @@
+// ============================================================
+// BACKGROUND BATCH JOB SUBSYSTEM
+// ============================================================
+//
+// Replaces the old synchronous `process_csv_batch` command. A job runs
+// `concurrency` rows at a time across their own worker threads, each
+// pulling the next pending row index off a shared queue and driving its
+// own `fetch_stitched_tile` / `run_ai_analysis` child process (tracked in
+// `jobmanager` so a single row can be killed without aborting the whole
+// job). Progress checkpoints to `batch_results/<job_id>.state.json` after
+// every row completes, in whatever order they finish in, so a crash or a
+// cancel only loses rows still in flight.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{fetch_stitched_tile, get_paths, run_ai_analysis, CsvRow, SolarDetection};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchJobState {
+    job_id: String,
+    rows: Vec<CsvRow>,
+    zoom: u32,
+    radius: u32,
+    provider: String,
+    model_name: String,
+    concurrency: usize,
+    // Indexed in parallel with `rows`; set as each row succeeds, in
+    // whatever order the worker threads complete it.
+    completed: Vec<bool>,
+    results: Vec<Option<SolarDetection>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BatchProgress {
+    job_id: String,
+    done: usize,
+    total: usize,
+    latest: Option<SolarDetection>,
+}
+
+fn cancel_flags() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static FLAGS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    FLAGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn batch_results_dir() -> Result<PathBuf, String> {
+    let (_tauri_dir, project_root) = get_paths()?;
+    let dir = project_root.join("batch_results");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn state_path(job_id: &str) -> Result<PathBuf, String> {
+    Ok(batch_results_dir()?.join(format!("{job_id}.state.json")))
+}
+
+// Write to a temp file and rename over the real checkpoint so a crash
+// mid-write never leaves a half-written `.state.json` behind.
+fn write_checkpoint(state: &BatchJobState) -> Result<(), String> {
+    let path = state_path(&state.job_id)?;
+    let tmp_path = path.with_extension("json.tmp");
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write checkpoint: {e}"))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to commit checkpoint: {e}"))?;
+    Ok(())
+}
+
+fn load_checkpoint(job_id: &str) -> Result<BatchJobState, String> {
+    let path = state_path(job_id)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("No checkpoint for job {job_id}: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Corrupt checkpoint for {job_id}: {e}"))
+}
+
+fn process_row(
+    job_id: &str,
+    row: &CsvRow,
+    idx: usize,
+    zoom: u32,
+    radius: u32,
+    provider: &str,
+    model_name: &str,
+) -> Result<SolarDetection, String> {
+    let row_job_id = format!("{job_id}-{idx}");
+
+    // `fetch_stitched_tile` / `run_ai_analysis` are async tauri commands;
+    // this function runs on a plain worker thread (one per batch
+    // `concurrency` slot), so bridge into the tauri-managed tokio runtime
+    // rather than making the whole worker-pool machinery async.
+    let image_b64 = tauri::async_runtime::block_on(fetch_stitched_tile(
+        row.lat,
+        row.lon,
+        zoom,
+        radius,
+        provider.to_string(),
+        Some(row_job_id.clone()),
+    ))?;
+    let ai_json = tauri::async_runtime::block_on(run_ai_analysis(
+        image_b64,
+        model_name.to_string(),
+        Some(row_job_id),
+    ))?;
+
+    let json_line = ai_json
+        .lines()
+        .find(|l| l.trim().starts_with('{'))
+        .ok_or_else(|| "AI output missing JSON".to_string())?;
+    let mut detection: SolarDetection =
+        serde_json::from_str(json_line).map_err(|e| e.to_string())?;
+
+    detection.sample_id = row.sample_id.clone();
+    detection.lat = row.lat;
+    detection.lon = row.lon;
+    detection.zoom = zoom;
+    detection.radius = radius;
+    detection.provider = provider.to_string();
+    detection.model_name = model_name.to_string();
+
+    Ok(detection)
+}
+
+fn run_job(app: AppHandle, state: BatchJobState, cancel: Arc<AtomicBool>) {
+    let total = state.rows.len();
+    let pending: VecDeque<usize> = (0..total).filter(|&i| !state.completed[i]).collect();
+    let pending = Arc::new(Mutex::new(pending));
+    let state = Arc::new(Mutex::new(state));
+    let concurrency = state.lock().unwrap().concurrency.max(1);
+
+    let mut workers = Vec::with_capacity(concurrency);
+    for _ in 0..concurrency {
+        let app = app.clone();
+        let cancel = cancel.clone();
+        let pending = pending.clone();
+        let state = state.clone();
+
+        workers.push(thread::spawn(move || loop {
+            if cancel.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let idx = match pending.lock().unwrap().pop_front() {
+                Some(idx) => idx,
+                None => break,
+            };
+
+            let (job_id, row, zoom, radius, provider, model_name) = {
+                let state = state.lock().unwrap();
+                (
+                    state.job_id.clone(),
+                    state.rows[idx].clone(),
+                    state.zoom,
+                    state.radius,
+                    state.provider.clone(),
+                    state.model_name.clone(),
+                )
+            };
+
+            let outcome = process_row(&job_id, &row, idx, zoom, radius, &provider, &model_name);
+
+            let mut state = state.lock().unwrap();
+
+            // Only a successful row is marked `completed`: `resume_batch_job`
+            // rebuilds its pending queue from `!completed[i]`, so a row left
+            // `false` here gets re-attempted on the next resume instead of
+            // being silently dropped from the output.
+            let detection = match outcome {
+                Ok(d) => d,
+                Err(e) => {
+                    let _ = app.emit("batch_error", format!("row {idx} ({}): {e}", row.sample_id));
+                    if let Err(e) = write_checkpoint(&state) {
+                        let _ = app.emit("batch_error", format!("checkpoint write failed: {e}"));
+                    }
+                    continue;
+                }
+            };
+
+            state.completed[idx] = true;
+            state.results[idx] = Some(detection.clone());
+
+            if let Err(e) = write_checkpoint(&state) {
+                let _ = app.emit("batch_error", format!("checkpoint write failed: {e}"));
+            }
+
+            let done = state.completed.iter().filter(|c| **c).count();
+            let _ = app.emit(
+                "batch_progress",
+                BatchProgress {
+                    job_id: state.job_id.clone(),
+                    done,
+                    total,
+                    latest: Some(detection),
+                },
+            );
+        }));
+    }
+
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    cancel_flags().lock().unwrap().remove(&state.lock().unwrap().job_id);
+}
+
+fn spawn_worker(app: AppHandle, state: BatchJobState) -> String {
+    let job_id = state.job_id.clone();
+    let cancel = Arc::new(AtomicBool::new(false));
+    cancel_flags().lock().unwrap().insert(job_id.clone(), cancel.clone());
+
+    thread::spawn(move || run_job(app, state, cancel));
+
+    job_id
+}
+
+#[tauri::command]
+pub fn start_batch_job(
+    app: AppHandle,
+    csv_path: String,
+    zoom: u32,
+    radius: u32,
+    provider: String,
+    model_name: String,
+    concurrency: usize,
+) -> Result<String, String> {
+    use csv::ReaderBuilder;
+
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(&csv_path)
+        .map_err(|e| format!("Failed to read CSV: {e}"))?;
+
+    let rows: Vec<CsvRow> = reader
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Bad CSV row: {e}"))?;
+
+    let job_id = format!("job_{}", chrono::Local::now().format("%Y%m%d_%H%M%S%3f"));
+    let row_count = rows.len();
+
+    let state = BatchJobState {
+        job_id: job_id.clone(),
+        rows,
+        zoom,
+        radius,
+        provider,
+        model_name,
+        concurrency: concurrency.max(1),
+        completed: vec![false; row_count],
+        results: vec![None; row_count],
+    };
+
+    write_checkpoint(&state)?;
+    Ok(spawn_worker(app, state))
+}
+
+#[tauri::command]
+pub fn resume_batch_job(app: AppHandle, job_id: String) -> Result<String, String> {
+    let state = load_checkpoint(&job_id)?;
+    Ok(spawn_worker(app, state))
+}
+
+#[tauri::command]
+pub fn list_batch_jobs() -> Result<Vec<String>, String> {
+    let dir = batch_results_dir()?;
+    let mut job_ids = Vec::new();
+
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(job_id) = name.strip_suffix(".state.json") {
+            job_ids.push(job_id.to_string());
+        }
+    }
+
+    job_ids.sort();
+    Ok(job_ids)
+}
+
+#[tauri::command]
+pub fn cancel_batch_job(job_id: String) -> Result<(), String> {
+    match cancel_flags().lock().unwrap().get(&job_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        None => Err(format!("No running job with id {job_id}")),
+    }
+}
@@ -0,0 +1,36 @@
+// ============================================================
+// STRUCTURED LOGGING
+// ============================================================
+//
+// Replaces the scattered `println!` debug statements with a `tracing`
+// subscriber that writes leveled logs to both stderr and a daily-rotating
+// `logs/solarverify.log`, set to `info` by default (override with
+// `RUST_LOG`). Subprocess-driving commands open a `job_id`-tagged span
+// while they wait on the child, so the log lines emitted meanwhile can be
+// grepped out of the shared log file; the child's own output is not logged.
+
+use std::path::Path;
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
+
+/// Must be held for the life of the process: dropping it stops the
+/// background thread that flushes the file writer.
+pub fn init(project_root: &Path) -> WorkerGuard {
+    let log_dir = project_root.join("logs");
+    let _ = std::fs::create_dir_all(&log_dir);
+
+    let file_appender = tracing_appender::rolling::daily(&log_dir, "solarverify.log");
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(fmt::layer().with_writer(file_writer).with_ansi(false))
+        .init();
+
+    guard
+}
@@ -1,7 +1,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{env, fs, path::PathBuf, process::Command};
+use std::{env, fs, path::PathBuf, process::Stdio, sync::Arc};
+
 use tauri::{command, Manager};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tracing::Instrument;
+
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -9,13 +14,27 @@ use tauri_plugin_dialog;
 use tauri_plugin_fs;
 use tauri_plugin_shell;
 
+mod benchmark;
+mod export;
+mod follow;
+mod geo;
+mod gpkg;
+mod inference;
+mod jobmanager;
+mod jobs;
+mod logging;
+mod models;
+mod signing;
+mod storage;
+mod streaming;
+
 
 
 // ============================================================
 // DATA STRUCTURES
 // ============================================================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct SolarDetection {
     // ADDED DEFAULTS TO ALL CSV/APP FIELDS
     #[serde(default)]
@@ -50,6 +69,9 @@ struct SolarDetection {
     #[serde(default)]
     provider: String,
 
+    #[serde(default)]
+    model_name: String,
+
     #[serde(default)]
     audit_overlay_path: Option<String>,
 
@@ -58,13 +80,13 @@ struct SolarDetection {
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ImageMetadata {
     source: String,
     capture_date: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct CsvRow {
     sample_id: String,
     lat: f64,
@@ -111,7 +133,7 @@ fn fetch_and_crop_tile(lat: f64, lon: f64, zoom: u32, radius: u32, provider: Str
         return Err(format!("imagenRunner.py missing at {}", script_path.display()));
     }
 
-    let output = Command::new(&python_path)
+    let output = std::process::Command::new(&python_path)
         .current_dir(&project_root)
         .arg(&script_path)
         .arg(lat.to_string())
@@ -143,10 +165,88 @@ fn fetch_and_crop_tile(lat: f64, lon: f64, zoom: u32, radius: u32, provider: Str
     Ok(format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(bytes)))
 }
 
+// Spawns `child`, registers it under `job_id` (if the caller supplied one)
+// for the duration of the wait so a concurrent `cancel_job(job_id)` call
+// can kill it, then returns its status plus captured stdout/stderr. Runs
+// under a span that tags the log lines emitted while waiting on the child
+// with its `job_id`; the child's own stdout/stderr are returned to the
+// caller, not written to the log.
+async fn run_tracked(
+    mut child: Child,
+    job_id: &Option<String>,
+) -> Result<(std::process::ExitStatus, String, String), String> {
+    let span = tracing::info_span!("subprocess", job_id = job_id.as_deref().unwrap_or("-"));
+
+    async move {
+        use tokio::io::AsyncReadExt;
+
+        let mut stdout_pipe = child.stdout.take();
+        let mut stderr_pipe = child.stderr.take();
+        let handle = Arc::new(Mutex::new(child));
+
+        if let Some(id) = job_id {
+            jobmanager::register(id.clone(), handle.clone()).await;
+        }
+
+        // The child's stdout/stderr pipes must be drained *while* we wait,
+        // not after: `run_model.py` can write more than the OS pipe buffer
+        // (a large polygon mask easily does), and with `Stdio::piped()` the
+        // child blocks on write once that buffer fills. Waiting to completion
+        // before reading the pipes deadlocks the child against us.
+        tracing::debug!("waiting on subprocess");
+        // Poll with `try_wait` instead of holding the lock across `wait()`,
+        // so `cancel_job` can take the lock and kill the child meanwhile.
+        let wait_fut = async {
+            loop {
+                let status = handle
+                    .lock()
+                    .await
+                    .try_wait()
+                    .map_err(|e| format!("Failed to wait on child process: {e}"))?;
+                if let Some(status) = status {
+                    return Ok::<_, String>(status);
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        };
+        let stdout_fut = async {
+            let mut buf = String::new();
+            if let Some(pipe) = stdout_pipe.as_mut() {
+                pipe.read_to_string(&mut buf).await.map_err(|e| e.to_string())?;
+            }
+            Ok::<_, String>(buf)
+        };
+        let stderr_fut = async {
+            let mut buf = String::new();
+            if let Some(pipe) = stderr_pipe.as_mut() {
+                pipe.read_to_string(&mut buf).await.map_err(|e| e.to_string())?;
+            }
+            Ok::<_, String>(buf)
+        };
+        let result = tokio::try_join!(wait_fut, stdout_fut, stderr_fut);
+
+        if let Some(id) = job_id {
+            jobmanager::remove(id).await;
+        }
+
+        let (status, stdout, stderr) = result?;
+        tracing::debug!(success = status.success(), "subprocess finished");
+
+        Ok((status, stdout, stderr))
+    }
+    .instrument(span)
+    .await
+}
+
 #[command]
-fn fetch_stitched_tile(lat: f64, lon: f64, zoom: u32, radius: u32, provider: String)
-    -> Result<String, String>
-{
+async fn fetch_stitched_tile(
+    lat: f64,
+    lon: f64,
+    zoom: u32,
+    radius: u32,
+    provider: String,
+    job_id: Option<String>,
+) -> Result<String, String> {
     let (_tauri_dir, project_root) = get_paths()?;
     let python_path = py();
 
@@ -155,7 +255,9 @@ fn fetch_stitched_tile(lat: f64, lon: f64, zoom: u32, radius: u32, provider: Str
         return Err(format!("imagenRunner.py missing: {}", script_path.display()));
     }
 
-    let output = Command::new(&python_path)
+    tracing::info!(python = %python_path.display(), script = %script_path.display(), "fetching stitched tile");
+
+    let child = Command::new(&python_path)
         .current_dir(&project_root)
         .arg(&script_path)
         .arg(lat.to_string())
@@ -164,12 +266,14 @@ fn fetch_stitched_tile(lat: f64, lon: f64, zoom: u32, radius: u32, provider: Str
         .arg(radius.to_string())
         .arg(provider)
         .arg("--crop")
-        .output()
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to spawn python: {e}"))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (status, stdout, _stderr) = run_tracked(child, &job_id).await?;
 
-    if !output.status.success() {
+    if !status.success() {
         return Err(format!("Python error: {}", stdout));
     }
 
@@ -179,26 +283,45 @@ fn fetch_stitched_tile(lat: f64, lon: f64, zoom: u32, radius: u32, provider: Str
     }
 
     let img_path = project_root.join(rel);
+    tracing::debug!(path = %img_path.display(), "python output path");
 
-    let bytes = fs::read(img_path).map_err(|e| e.to_string())?;
+    let bytes = tokio::fs::read(img_path)
+        .await
+        .map_err(|e| e.to_string())?;
     Ok(format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(bytes)))
 }
 
 #[command]
-fn run_ai_analysis(image_b64: String) -> Result<String, String> {
+async fn run_ai_analysis(
+    image_b64: String,
+    model_name: String,
+    job_id: Option<String>,
+) -> Result<String, String> {
     let (_tauri_dir, project_root) = get_paths()?;
     let python_path = py();
+    let model_entry = models::resolve(&project_root, &model_name)?;
+
+    #[cfg(feature = "native-inference")]
+    if inference::use_native() {
+        let model = project_root.join(
+            PathBuf::from(&model_entry.weights_path).with_extension("onnx"),
+        );
+        let image_bytes = inference::decode_png(&image_b64)?;
+        let detection = inference::native::run(&image_bytes, &model, model_entry.input_size)?;
+        return serde_json::to_string(&detection).map_err(|e| e.to_string());
+    }
 
     let tmp = project_root.join("tmp_input.png");
     let script = project_root.join("run_model.py");
-    let model = project_root.join("verifier1.pt");
+    let model = project_root.join(&model_entry.weights_path);
 
-    fs::write(
-        &tmp,
-        general_purpose::STANDARD.decode(
-            image_b64.replace("data:image/png;base64,", "")
-        ).map_err(|e| e.to_string())?
-    ).map_err(|e| format!("Failed to write PNG: {e}"))?;
+    let image_bytes = general_purpose::STANDARD
+        .decode(image_b64.replace("data:image/png;base64,", ""))
+        .map_err(|e| e.to_string())?;
+
+    tokio::fs::write(&tmp, image_bytes)
+        .await
+        .map_err(|e| format!("Failed to write PNG: {e}"))?;
 
     if !script.exists() {
         return Err(format!("run_model.py missing: {}", script.display()));
@@ -207,80 +330,23 @@ fn run_ai_analysis(image_b64: String) -> Result<String, String> {
         return Err(format!("Model file missing: {}", model.display()));
     }
 
-    let output = Command::new(&python_path)
+    let child = Command::new(&python_path)
         .arg(&script)
         .arg(&tmp)
         .arg(&model)
-        .output()
+        .arg(model_entry.default_confidence_threshold.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to run AI script: {e}"))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (status, stdout, stderr) = run_tracked(child, &job_id).await?;
 
-    if !output.status.success() {
+    if !status.success() {
         return Err(format!("AI script error: {}", stderr));
     }
 
-    Ok(stdout.to_string())
-}
-
-#[command]
-fn process_csv_batch(
-    csv_path: String,
-    zoom: u32,
-    radius: u32,
-    provider: String,
-) -> Result<Vec<SolarDetection>, String> {
-    use csv::ReaderBuilder;
-
-    // Figure out paths
-    let (project_root, gui_dir) = get_paths()?;
-
-    // Open CSV
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(&csv_path)
-        .map_err(|e| format!("Failed to read CSV: {}", e))?;
-
-    let mut results = Vec::new();
-
-    for row in reader.deserialize() {
-        let row: CsvRow = row.map_err(|e| format!("Bad CSV row: {}", e))?;
-
-        // Fetch stitched tile
-        let tile_b64 = fetch_stitched_tile(
-            row.lat,
-            row.lon,
-            zoom,
-            radius,
-            provider.clone(),
-        )?;
-
-        // Run AI on that
-        let ai_json = run_ai_analysis(tile_b64)?;
-
-        // Extract JSON line from stdout
-        let json_line = ai_json
-            .lines()
-            .find(|l| l.trim().starts_with('{'))
-            .ok_or("AI output missing JSON")?;
-
-        let mut det: SolarDetection =
-            serde_json::from_str(json_line)
-                .map_err(|e| format!("AI JSON parse error: {}", e))?;
-
-        // Fill metadata
-        det.sample_id = row.sample_id;
-        det.lat = row.lat;
-        det.lon = row.lon;
-        det.zoom = zoom;
-        det.radius = radius;
-        det.provider = provider.clone();
-
-        results.push(det);
-    }
-
-    Ok(results)
+    Ok(stdout)
 }
 
 #[command]
@@ -289,104 +355,89 @@ fn save_batch_results(
     batch_name: String,
 ) -> Result<String, String> {
     let (_tauri_dir, project_root) = get_paths()?;
+    let backend = storage::default_backend(project_root.clone())?;
 
-    
-    let output_dir = project_root.join("batch_results");
-    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-    
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("{}_{}.json", batch_name, timestamp);
-    let output_path = output_dir.join(&filename);
-    
+    let key = format!("batch_results/{}_{}.json", batch_name, timestamp);
+
     let json_string = serde_json::to_string_pretty(&detections)
         .map_err(|e| format!("Failed to serialize: {}", e))?;
-    
-    std::fs::write(&output_path, json_string)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    
-    Ok(output_path.to_string_lossy().to_string())
+
+    backend.write(&key, json_string.as_bytes())?;
+
+    let envelope = signing::sign(&detections, &project_root)?;
+    let envelope_json = serde_json::to_string_pretty(&envelope).map_err(|e| e.to_string())?;
+    backend.write(&signing::sidecar_key(&key), envelope_json.as_bytes())?;
+
+    Ok(key)
 }
 
 
 #[command]
 fn load_overlay_image(image_path: String) -> Result<String, String> {
-    let path = PathBuf::from(&image_path);
-
-    if !path.exists() {
-        return Err(format!("Overlay missing: {}", image_path));
-    }
+    let (_tauri_dir, project_root) = get_paths()?;
+    let backend = storage::default_backend(project_root)?;
 
-    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let bytes = backend.read(&image_path)?;
     Ok(format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(bytes)))
 }
 
 #[command]
 fn save_detection_json(data: SolarDetection, filename: String) -> Result<String, String> {
     let (_tauri_dir, project_root) = get_paths()?;
+    let backend = storage::default_backend(project_root.clone())?;
 
-    let dir = project_root.join("detections");
-    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-
-    let path = dir.join(&filename);
-
+    let key = format!("detections/{filename}");
     let json_string = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
-    fs::write(&path, json_string).map_err(|e| e.to_string())?;
+    backend.write(&key, json_string.as_bytes())?;
 
-    Ok(path.display().to_string())
+    let envelope = signing::sign(&data, &project_root)?;
+    let envelope_json = serde_json::to_string_pretty(&envelope).map_err(|e| e.to_string())?;
+    backend.write(&signing::sidecar_key(&key), envelope_json.as_bytes())?;
+
+    Ok(key)
 }
 
 #[command]
 fn save_audit_overlay(image_path: String, sample_id: String) -> Result<String, String> {
     let (_tauri_dir, project_root) = get_paths()?;
+    let backend = storage::default_backend(project_root)?;
 
-    let dir = project_root.join("audit_overlays");
-    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let key = format!(
+        "audit_overlays/audit_{}_{}.png",
+        sample_id,
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    );
 
-    let filename = format!("audit_{}_{}.png", sample_id, chrono::Local::now().format("%Y%m%d_%H%M%S"));
-    let out = dir.join(filename);
+    let bytes = fs::read(&image_path).map_err(|e| e.to_string())?;
+    backend.write(&key, &bytes)?;
 
-    fs::copy(&image_path, &out).map_err(|e| e.to_string())?;
-    Ok(out.display().to_string())
+    Ok(key)
 }
 
 #[command]
 fn clear_tile_cache() -> Result<String, String> {
     let (_tauri_dir, project_root) = get_paths()?;
-    let cache_dir = project_root.join("tile_cache");
+    let backend = storage::default_backend(project_root)?;
 
-    if cache_dir.exists() {
-        fs::remove_dir_all(&cache_dir).map_err(|e| e.to_string())?;
-    }
+    backend.delete("tile_cache")?;
 
     Ok("Cache cleared".into())
 }
 
 #[command]
-fn get_cache_size() -> Result<u64, String> {
+async fn get_cache_size() -> Result<u64, String> {
     let (_tauri_dir, project_root) = get_paths()?;
-    let cache_dir = project_root.join("tile_cache");
 
-    if !cache_dir.exists() {
-        return Ok(0);
-    }
-
-    let mut total = 0u64;
-
-    fn walk(path: &PathBuf, total: &mut u64) -> std::io::Result<()> {
-        for entry in fs::read_dir(path)? {
-            let e = entry?;
-            let p = e.path();
-            if p.is_dir() {
-                walk(&p, total)?;
-            } else {
-                *total += e.metadata()?.len();
-            }
-        }
-        Ok(())
-    }
-
-    walk(&cache_dir, &mut total).map_err(|e| e.to_string())?;
-    Ok(total)
+    // `StorageBackend` is a sync trait (the S3 variant blocks on its own
+    // internal runtime), so run it on the blocking pool rather than the
+    // async worker that's servicing other commands.
+    tauri::async_runtime::spawn_blocking(move || {
+        let backend = storage::default_backend(project_root)?;
+        backend.size("tile_cache")
+    })
+    .await
+    .map_err(|e| format!("Cache size task panicked: {e}"))?
 }
 
 #[command]
@@ -404,6 +455,7 @@ fn add_to_training_data(detection: SolarDetection) -> Result<String, String> {
 
     data.push(json!({
         "timestamp": chrono::Local::now().to_rfc3339(),
+        "model_name": detection.model_name,
         "confidence": detection.confidence,
         "panel_count": detection.panel_count_est,
         "area_sqm": detection.pv_area_sqm_est,
@@ -423,6 +475,9 @@ fn add_to_training_data(detection: SolarDetection) -> Result<String, String> {
 // ============================================================
 
 fn main() {
+    let (_, project_root) = get_paths().expect("Failed to resolve project paths");
+    let _log_guard = logging::init(&project_root);
+
     tauri::Builder::default()
     .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
@@ -443,8 +498,18 @@ fn main() {
             clear_tile_cache,
             get_cache_size,
             add_to_training_data,
-            process_csv_batch,
-            save_batch_results
+            save_batch_results,
+            jobs::start_batch_job,
+            jobs::resume_batch_job,
+            jobs::list_batch_jobs,
+            jobs::cancel_batch_job,
+            streaming::start_streaming_batch,
+            streaming::resume_batch,
+            export::export_detections,
+            signing::verify_detection,
+            jobmanager::cancel_job,
+            models::list_models,
+            benchmark::run_benchmark
         ])
         .run(tauri::generate_context!())
         .expect("error running Tauri app");
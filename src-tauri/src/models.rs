@@ -0,0 +1,68 @@
+// ============================================================
+// DETECTOR MODEL REGISTRY
+// ============================================================
+//
+// Lets `run_ai_analysis` and batch jobs pick between more than one set of
+// detector weights instead of the hardcoded `verifier1.pt`. The manifest
+// lives at `models.json` next to the weight files; if it doesn't exist
+// yet, a single entry describing the originally-bundled `verifier1.pt` is
+// generated on first use, so upgrading an existing install doesn't
+// require hand-authoring one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelEntry {
+    pub name: String,
+    pub weights_path: String,
+    pub input_size: u32,
+    pub default_confidence_threshold: f64,
+    pub description: String,
+}
+
+fn manifest_path(models_root: &Path) -> PathBuf {
+    models_root.join("models.json")
+}
+
+fn default_manifest() -> Vec<ModelEntry> {
+    vec![ModelEntry {
+        name: "default".to_string(),
+        weights_path: "verifier1.pt".to_string(),
+        input_size: 512,
+        default_confidence_threshold: 0.5,
+        description: "Originally-bundled verifier weights.".to_string(),
+    }]
+}
+
+/// Loads `models.json`, generating (and persisting) a single-entry
+/// manifest describing the bundled `verifier1.pt` on first use if none
+/// exists yet.
+pub fn load(models_root: &Path) -> Result<Vec<ModelEntry>, String> {
+    let path = manifest_path(models_root);
+
+    if !path.exists() {
+        let manifest = default_manifest();
+        let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        return Ok(manifest);
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Corrupt models.json: {e}"))
+}
+
+pub fn resolve(models_root: &Path, model_name: &str) -> Result<ModelEntry, String> {
+    load(models_root)?
+        .into_iter()
+        .find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Unknown model: {model_name}"))
+}
+
+#[tauri::command]
+pub fn list_models() -> Result<Vec<ModelEntry>, String> {
+    let (_tauri_dir, project_root) = crate::get_paths()?;
+    load(&project_root)
+}
@@ -0,0 +1,152 @@
+// ============================================================
+// TAMPER-EVIDENT SIGNED DETECTION RECORDS
+// ============================================================
+//
+// `save_detection_json` / `save_batch_results` write plain files that
+// anyone can silently edit afterward, which undermines the audit trail a
+// verification tool is supposed to produce. Every save now also writes a
+// `<file>.sig.json` sidecar: an Ed25519 signature over a canonical
+// (sorted-key) JSON serialization of the record, plus the signer's public
+// key and an ISO-8601 timestamp. `verify_detection` recomputes the same
+// canonical bytes and checks the signature still matches.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignatureEnvelope {
+    pub public_key: String,
+    pub signature: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyResult {
+    pub valid: bool,
+    pub signer: Option<String>,
+    pub reason: Option<String>,
+}
+
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<String, Value> = map
+                .iter()
+                .map(|(k, v)| (k.clone(), canonicalize(v)))
+                .collect();
+            serde_json::to_value(sorted).unwrap()
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+fn canonical_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let raw = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    serde_json::to_vec(&canonicalize(&raw)).map_err(|e| e.to_string())
+}
+
+fn key_path(project_root: &Path) -> PathBuf {
+    std::env::var("SOLARVERIFY_SIGNING_KEY")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| project_root.join("signing_key.ed25519"))
+}
+
+fn signing_key(project_root: &Path) -> Result<&'static SigningKey, String> {
+    static KEY: OnceLock<SigningKey> = OnceLock::new();
+    if let Some(key) = KEY.get() {
+        return Ok(key);
+    }
+
+    let path = key_path(project_root);
+
+    let key = if path.exists() {
+        let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+        let arr: [u8; 32] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("Signing key at {} must be 32 bytes", path.display()))?;
+        SigningKey::from_bytes(&arr)
+    } else {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, key.to_bytes()).map_err(|e| e.to_string())?;
+        key
+    };
+
+    Ok(KEY.get_or_init(|| key))
+}
+
+/// Signs `value`'s canonical JSON form, generating (and persisting) the
+/// project's Ed25519 keypair on first use if none exists yet.
+pub fn sign<T: Serialize>(value: &T, project_root: &Path) -> Result<SignatureEnvelope, String> {
+    let bytes = canonical_bytes(value)?;
+    let key = signing_key(project_root)?;
+    let signature = key.sign(&bytes);
+
+    Ok(SignatureEnvelope {
+        public_key: hex::encode(key.verifying_key().to_bytes()),
+        signature: hex::encode(signature.to_bytes()),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+pub fn sidecar_key(key: &str) -> String {
+    format!("{key}.sig.json")
+}
+
+#[tauri::command]
+pub fn verify_detection(path: String) -> Result<VerifyResult, String> {
+    let data_path = Path::new(&path);
+    let sidecar_path = PathBuf::from(format!("{path}.sig.json"));
+
+    let record_bytes = fs::read(data_path)
+        .map_err(|e| format!("Failed to read {}: {e}", data_path.display()))?;
+    let record: Value = serde_json::from_slice(&record_bytes).map_err(|e| e.to_string())?;
+    let canon_bytes = serde_json::to_vec(&canonicalize(&record)).map_err(|e| e.to_string())?;
+
+    let envelope_str = fs::read_to_string(&sidecar_path).map_err(|e| {
+        format!(
+            "No signature sidecar at {}: {e}",
+            sidecar_path.display()
+        )
+    })?;
+    let envelope: SignatureEnvelope =
+        serde_json::from_str(&envelope_str).map_err(|e| e.to_string())?;
+
+    let pubkey_bytes = hex::decode(&envelope.public_key).map_err(|e| e.to_string())?;
+    let sig_bytes = hex::decode(&envelope.signature).map_err(|e| e.to_string())?;
+
+    let pubkey_arr: [u8; 32] = pubkey_bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "Malformed public key in sidecar".to_string())?;
+    let sig_arr: [u8; 64] = sig_bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "Malformed signature in sidecar".to_string())?;
+
+    let verifying_key = VerifyingKey::from_bytes(&pubkey_arr).map_err(|e| e.to_string())?;
+    let signature = Signature::from_bytes(&sig_arr);
+
+    match verifying_key.verify(&canon_bytes, &signature) {
+        Ok(()) => Ok(VerifyResult {
+            valid: true,
+            signer: Some(envelope.public_key),
+            reason: None,
+        }),
+        Err(_) => Ok(VerifyResult {
+            valid: false,
+            signer: Some(envelope.public_key),
+            reason: Some("Signature does not match the record's current contents".into()),
+        }),
+    }
+}
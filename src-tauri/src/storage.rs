@@ -0,0 +1,254 @@
+// ============================================================
+// PLUGGABLE STORAGE BACKEND
+// ============================================================
+//
+// `save_batch_results`, `save_detection_json`, `save_audit_overlay`,
+// `load_overlay_image`, `clear_tile_cache`, and `get_cache_size` all used
+// to hardcode `project_root.join(...)`. Routing them through this trait
+// lets a deployment swap local disk for a shared S3-compatible bucket
+// without touching the commands themselves.
+
+use std::path::{Path, PathBuf};
+
+pub trait StorageBackend: Send + Sync {
+    fn read(&self, key: &str) -> Result<Vec<u8>, String>;
+    fn write(&self, key: &str, data: &[u8]) -> Result<(), String>;
+    fn delete(&self, key: &str) -> Result<(), String>;
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String>;
+    fn size(&self, prefix: &str) -> Result<u64, String>;
+}
+
+pub struct LocalFs {
+    root: PathBuf,
+}
+
+impl LocalFs {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl StorageBackend for LocalFs {
+    fn read(&self, key: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(self.path_for(key)).map_err(|e| e.to_string())
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        let path = self.path_for(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path_for(key);
+        if path.is_dir() {
+            std::fs::remove_dir_all(path).map_err(|e| e.to_string())
+        } else if path.exists() {
+            std::fs::remove_file(path).map_err(|e| e.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let dir = self.path_for(prefix);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        fn walk(base: &Path, dir: &Path, keys: &mut Vec<String>) -> std::io::Result<()> {
+            for entry in std::fs::read_dir(dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(base, &path, keys)?;
+                } else if let Ok(rel) = path.strip_prefix(base) {
+                    keys.push(rel.to_string_lossy().replace('\\', "/"));
+                }
+            }
+            Ok(())
+        }
+
+        let mut keys = Vec::new();
+        walk(&self.root, &dir, &mut keys).map_err(|e| e.to_string())?;
+        Ok(keys)
+    }
+
+    fn size(&self, prefix: &str) -> Result<u64, String> {
+        let dir = self.path_for(prefix);
+        if !dir.exists() {
+            return Ok(0);
+        }
+
+        fn walk(dir: &Path, total: &mut u64) -> std::io::Result<()> {
+            for entry in std::fs::read_dir(dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(&path, total)?;
+                } else {
+                    *total += entry.metadata()?.len();
+                }
+            }
+            Ok(())
+        }
+
+        let mut total = 0u64;
+        walk(&dir, &mut total).map_err(|e| e.to_string())?;
+        Ok(total)
+    }
+}
+
+pub struct S3Backend {
+    bucket: String,
+    prefix: String,
+    client: aws_sdk_s3::Client,
+    rt: tokio::runtime::Runtime,
+}
+
+impl S3Backend {
+    pub fn from_env() -> Result<Self, String> {
+        let bucket = std::env::var("SOLARVERIFY_S3_BUCKET")
+            .map_err(|_| "SOLARVERIFY_S3_BUCKET not set".to_string())?;
+        let prefix = std::env::var("SOLARVERIFY_S3_PREFIX").unwrap_or_default();
+
+        let rt = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+        let client = rt.block_on(async {
+            let config = aws_config::load_from_env().await;
+            aws_sdk_s3::Client::new(&config)
+        });
+
+        Ok(Self { bucket, prefix, client, rt })
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix.trim_end_matches('/'), key)
+        }
+    }
+}
+
+impl StorageBackend for S3Backend {
+    fn read(&self, key: &str) -> Result<Vec<u8>, String> {
+        let full_key = self.full_key(key);
+        self.rt.block_on(async {
+            let resp = self
+                .client
+                .get_object()
+                .bucket(&self.bucket)
+                .key(&full_key)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let bytes = resp.body.collect().await.map_err(|e| e.to_string())?;
+            Ok(bytes.into_bytes().to_vec())
+        })
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<(), String> {
+        let full_key = self.full_key(key);
+        self.rt.block_on(async {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&full_key)
+                .body(data.to_vec().into())
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        })
+    }
+
+    // `key` is treated as a prefix (matching `list`/`size`) rather than an
+    // exact object key: `clear_tile_cache` calls this with `"tile_cache"`,
+    // but every stored tile lives under `tile_cache/...`, so a bare
+    // `delete_object` on the prefix itself matches nothing and reports
+    // success while leaving every tile in place.
+    fn delete(&self, key: &str) -> Result<(), String> {
+        let keys = self.list(key)?;
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        self.rt.block_on(async {
+            let objects = keys
+                .iter()
+                .map(|k| {
+                    aws_sdk_s3::types::ObjectIdentifier::builder()
+                        .key(k)
+                        .build()
+                        .map_err(|e| e.to_string())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // `tile_cache` is expected to stay well under S3's 1000-key
+            // DeleteObjects limit per request, so no batching here.
+            let delete = aws_sdk_s3::types::Delete::builder()
+                .set_objects(Some(objects))
+                .build()
+                .map_err(|e| e.to_string())?;
+
+            self.client
+                .delete_objects()
+                .bucket(&self.bucket)
+                .delete(delete)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        })
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let full_prefix = self.full_key(prefix);
+        self.rt.block_on(async {
+            let resp = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(&full_prefix)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(resp
+                .contents()
+                .iter()
+                .filter_map(|o| o.key().map(|s| s.to_string()))
+                .collect())
+        })
+    }
+
+    fn size(&self, prefix: &str) -> Result<u64, String> {
+        let full_prefix = self.full_key(prefix);
+        self.rt.block_on(async {
+            let resp = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(&full_prefix)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(resp.contents().iter().map(|o| o.size().unwrap_or(0) as u64).sum())
+        })
+    }
+}
+
+/// `LocalFs` rooted at `root`, unless `SOLARVERIFY_S3_BUCKET` is set, in
+/// which case every caller shares one S3-compatible bucket instead.
+pub fn default_backend(root: PathBuf) -> Result<Box<dyn StorageBackend>, String> {
+    if std::env::var("SOLARVERIFY_S3_BUCKET").is_ok() {
+        Ok(Box::new(S3Backend::from_env()?))
+    } else {
+        Ok(Box::new(LocalFs::new(root)))
+    }
+}
@@ -0,0 +1,231 @@
+// ============================================================
+// STREAMING NDJSON BATCH JOBS
+// ============================================================
+//
+// Alternative to the in-process worker in `jobs.rs`: the Python side does
+// the fetch + inference work itself and appends one NDJSON line per
+// completed row to `batch_results/batch_<job_id>.ndjson`. Rust tails that
+// file with `follow::FollowReader`, emitting each `SolarDetection` (plus a
+// `batch_progress` event) to the frontend as soon as the line lands. The
+// NDJSON file doubles as the checkpoint: `resume_batch` rescans it for
+// already-completed `sample_id`s and only re-submits what's left.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::follow::{completed_sample_ids, FollowReader, Line};
+use crate::{get_paths, py, CsvRow};
+
+#[derive(Debug, Clone, Serialize)]
+struct StreamProgress {
+    job_id: String,
+    done: usize,
+    total: usize,
+    current_sample_id: Option<String>,
+}
+
+fn ndjson_dir() -> Result<PathBuf, String> {
+    let (_tauri_dir, project_root) = get_paths()?;
+    let dir = project_root.join("batch_results");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn load_rows(csv_path: &str) -> Result<Vec<CsvRow>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(csv_path)
+        .map_err(|e| format!("Failed to read CSV: {e}"))?;
+    reader
+        .deserialize()
+        .collect::<Result<Vec<CsvRow>, _>>()
+        .map_err(|e| format!("Bad CSV row: {e}"))
+}
+
+fn spawn_python_batch(
+    rows: &[CsvRow],
+    zoom: u32,
+    radius: u32,
+    provider: &str,
+    ndjson_path: &Path,
+    skip_ids: &HashSet<String>,
+) -> Result<Child, String> {
+    let (_tauri_dir, project_root) = get_paths()?;
+    let script = project_root.join("batch_runner.py");
+
+    // Rows already completed in a prior run are filtered out here rather
+    // than taught to the python side, so `batch_runner.py` never needs to
+    // know about resume semantics.
+    let pending_csv = ndjson_path.with_extension("pending.csv");
+    let mut writer = csv::Writer::from_path(&pending_csv).map_err(|e| e.to_string())?;
+    for row in rows {
+        if !skip_ids.contains(&row.sample_id) {
+            writer.serialize(row).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.flush().map_err(|e| e.to_string())?;
+
+    Command::new(py())
+        .arg(&script)
+        .arg(&pending_csv)
+        .arg(zoom.to_string())
+        .arg(radius.to_string())
+        .arg(provider)
+        .arg(ndjson_path)
+        .arg(if skip_ids.is_empty() { "w" } else { "a" })
+        .spawn()
+        .map_err(|e| format!("Failed to spawn batch_runner.py: {e}"))
+}
+
+fn run_streaming_job(
+    app: AppHandle,
+    job_id: String,
+    total: usize,
+    already_done: usize,
+    ndjson_path: PathBuf,
+    mut child: Child,
+) {
+    for _ in 0..100 {
+        if ndjson_path.exists() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let mut follower = match FollowReader::open(&ndjson_path) {
+        Ok(f) => f,
+        Err(e) => {
+            let _ = app.emit("batch_error", format!("job {job_id}: {e}"));
+            let _ = child.kill();
+            return;
+        }
+    };
+
+    // On resume, `ndjson_path` is the same file the prior run appended to,
+    // carrying lines for every `sample_id` in `skip_ids` already. Start
+    // reading from the current end of file so those rows are counted (via
+    // `already_done`) but not re-decoded and re-emitted to the frontend.
+    if already_done > 0 {
+        if let Err(e) = follower.seek_to_end() {
+            let _ = app.emit("batch_error", format!("job {job_id}: {e}"));
+            let _ = child.kill();
+            return;
+        }
+    }
+
+    let mut done = already_done;
+    let mut exited = None;
+    loop {
+        match follower.poll_line() {
+            Ok(Line::Detection(detection)) => {
+                done += 1;
+                let sample_id = detection.sample_id.clone();
+                let _ = app.emit("detection", detection);
+                let _ = app.emit(
+                    "batch_progress",
+                    StreamProgress {
+                        job_id: job_id.clone(),
+                        done,
+                        total,
+                        current_sample_id: Some(sample_id),
+                    },
+                );
+            }
+            Ok(Line::Done) => break,
+            Ok(Line::Pending) => {
+                // No new line yet. If `batch_runner.py` has already exited
+                // without ever writing the `__batch_done__` sentinel (crash,
+                // OOM, unhandled exception), it never will — stop polling
+                // and surface the failure instead of looping forever. Its
+                // last lines may land between the poll above and the exit
+                // check, so one more poll runs before reporting it.
+                if let Some(status) = exited {
+                    let _ = app.emit(
+                        "batch_error",
+                        format!("job {job_id}: batch_runner.py exited ({status}) before completion"),
+                    );
+                    return;
+                }
+                match child.try_wait() {
+                    Ok(Some(status)) => exited = Some(status),
+                    Ok(None) => thread::sleep(Duration::from_millis(200)),
+                    Err(e) => {
+                        let _ = app.emit("batch_error", format!("job {job_id}: {e}"));
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = app.emit("batch_error", format!("job {job_id}: {e}"));
+                let _ = child.kill();
+                break;
+            }
+        }
+    }
+
+    let _ = child.wait();
+}
+
+#[tauri::command]
+pub fn start_streaming_batch(
+    app: AppHandle,
+    csv_path: String,
+    zoom: u32,
+    radius: u32,
+    provider: String,
+) -> Result<String, String> {
+    let rows = load_rows(&csv_path)?;
+    let total = rows.len();
+
+    let job_id = chrono::Local::now().format("%Y%m%d_%H%M%S%3f").to_string();
+    let ndjson_path = ndjson_dir()?.join(format!("batch_{job_id}.ndjson"));
+
+    let child = spawn_python_batch(&rows, zoom, radius, &provider, &ndjson_path, &HashSet::new())?;
+    let job_id_for_thread = job_id.clone();
+    thread::spawn(move || run_streaming_job(app, job_id_for_thread, total, 0, ndjson_path, child));
+
+    Ok(job_id)
+}
+
+#[tauri::command]
+pub fn resume_batch(
+    app: AppHandle,
+    ndjson_path: String,
+    csv_path: String,
+    zoom: u32,
+    radius: u32,
+    provider: String,
+) -> Result<String, String> {
+    let path = PathBuf::from(&ndjson_path);
+    let skip_ids = if path.exists() {
+        completed_sample_ids(&path)?
+    } else {
+        HashSet::new()
+    };
+
+    let rows = load_rows(&csv_path)?;
+    let total = rows.len();
+
+    let job_id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("resumed")
+        .trim_start_matches("batch_")
+        .to_string();
+
+    let already_done = skip_ids.len();
+    let child = spawn_python_batch(&rows, zoom, radius, &provider, &path, &skip_ids)?;
+    let job_id_for_thread = job_id.clone();
+    thread::spawn(move || {
+        run_streaming_job(app, job_id_for_thread, total, already_done, path, child)
+    });
+
+    Ok(job_id)
+}